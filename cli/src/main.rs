@@ -1,13 +1,68 @@
-use clap::Parser;
+use std::fs::File;
+use std::io;
+use std::path::PathBuf;
 
-use duckdb_polars::{export::duckdb::Connection, query_to_df_polars, DuckDBPolarsError};
+use clap::{Parser, ValueEnum};
+
+use duckdb_polars::{
+    export::{
+        duckdb::{params_from_iter, Connection},
+        write_df, ResultFormat,
+    },
+    query_to_df_polars_params,
+    sql::DuckDBPolars,
+    DuckDBPolarsError,
+};
 use tracing::{info, instrument};
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    Json,
+    JsonLines,
+    Csv,
+    Parquet,
+    Ipc,
+}
+
+impl From<Format> for ResultFormat {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Json => ResultFormat::Json,
+            Format::JsonLines => ResultFormat::JsonLines,
+            Format::Csv => ResultFormat::Csv,
+            Format::Parquet => ResultFormat::Parquet,
+            Format::Ipc => ResultFormat::Ipc,
+        }
+    }
+}
+
 #[derive(Debug, Parser)]
 #[command(author, version, about, long_about = None)]
 struct Args {
     #[clap(short, long, help = "SQL query to run")]
     sql: String,
+
+    #[clap(
+        long = "param",
+        help = "Positional parameter to bind to a `?` placeholder in --sql, may be repeated"
+    )]
+    params: Vec<String>,
+
+    #[clap(long, value_enum, default_value_t = Format::Json, help = "Output format")]
+    format: Format,
+
+    #[clap(
+        short,
+        long,
+        help = "Output file path; defaults to stdout when omitted"
+    )]
+    output: Option<PathBuf>,
+
+    #[clap(
+        long = "post-sql",
+        help = "Further query to run against the result in-process via Polars, without going back through DuckDB"
+    )]
+    post_sql: Option<String>,
 }
 
 impl Args {
@@ -15,11 +70,26 @@ impl Args {
     pub fn run(&self) -> Result<(), DuckDBPolarsError> {
         let conn = Connection::open_in_memory().expect("Failed to open connection");
         info!("Running query: {}", self.sql);
-        let df = query_to_df_polars(&conn, &self.sql)?;
-        info!("Output df: {}", df);
+        let mut df = query_to_df_polars_params(&conn, &self.sql, params_from_iter(&self.params))?;
+
+        if let Some(post_sql) = &self.post_sql {
+            let mut ctx = DuckDBPolars::new();
+            ctx.register("result", df);
+            info!("Running post-SQL: {}", post_sql);
+            df = ctx.sql(post_sql)?;
+        }
+
         info!("df schema: {:#?}", df.schema());
 
-        Ok(())
+        match &self.output {
+            Some(path) => {
+                let file = File::create(path).map_err(|e| DuckDBPolarsError::Internal {
+                    msg: format!("Failed to create output file {}: {}", path.display(), e),
+                })?;
+                write_df(&mut df, self.format.into(), file)
+            }
+            None => write_df(&mut df, self.format.into(), io::stdout()),
+        }
     }
 }
 
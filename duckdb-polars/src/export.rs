@@ -0,0 +1,105 @@
+//! Result serialization for consumers of this crate (e.g. the `cli` binary), plus a re-export
+//! of the underlying `duckdb` types so callers don't need to depend on the `duckdb` crate
+//! directly.
+
+use std::io::Write;
+
+use polars::prelude::*;
+
+pub use duckdb;
+
+use crate::DuckDBPolarsError;
+
+/// Output format [`write_df`] can serialize a `DataFrame` to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultFormat {
+    Json,
+    JsonLines,
+    Csv,
+    Parquet,
+    Ipc,
+}
+
+/// Builds the Polars writer for one [`ResultFormat`]. New formats are added by implementing
+/// this trait and registering them in [`factory_for`], without touching [`write_df`] itself.
+trait WriterFactory {
+    fn write(&self, df: &mut DataFrame, writer: &mut dyn Write) -> PolarsResult<()>;
+}
+
+struct JsonWriterFactory {
+    format: JsonFormat,
+}
+
+impl WriterFactory for JsonWriterFactory {
+    fn write(&self, df: &mut DataFrame, writer: &mut dyn Write) -> PolarsResult<()> {
+        JsonWriter::new(writer)
+            .with_json_format(self.format)
+            .finish(df)
+    }
+}
+
+struct CsvWriterFactory;
+
+impl WriterFactory for CsvWriterFactory {
+    fn write(&self, df: &mut DataFrame, writer: &mut dyn Write) -> PolarsResult<()> {
+        CsvWriter::new(writer).finish(df)
+    }
+}
+
+struct ParquetWriterFactory;
+
+impl WriterFactory for ParquetWriterFactory {
+    fn write(&self, df: &mut DataFrame, writer: &mut dyn Write) -> PolarsResult<()> {
+        ParquetWriter::new(writer).finish(df).map(|_| ())
+    }
+}
+
+struct IpcWriterFactory;
+
+impl WriterFactory for IpcWriterFactory {
+    fn write(&self, df: &mut DataFrame, writer: &mut dyn Write) -> PolarsResult<()> {
+        IpcWriter::new(writer).finish(df)
+    }
+}
+
+fn factory_for(format: ResultFormat) -> Box<dyn WriterFactory> {
+    match format {
+        ResultFormat::Json => Box::new(JsonWriterFactory {
+            format: JsonFormat::Json,
+        }),
+        ResultFormat::JsonLines => Box::new(JsonWriterFactory {
+            format: JsonFormat::JsonLines,
+        }),
+        ResultFormat::Csv => Box::new(CsvWriterFactory),
+        ResultFormat::Parquet => Box::new(ParquetWriterFactory),
+        ResultFormat::Ipc => Box::new(IpcWriterFactory),
+    }
+}
+
+/// Serializes `df` to `writer` in the requested `format`, dispatching to the matching
+/// [`WriterFactory`].
+pub fn write_df(
+    df: &mut DataFrame,
+    format: ResultFormat,
+    mut writer: impl Write,
+) -> Result<(), DuckDBPolarsError> {
+    factory_for(format)
+        .write(df, &mut writer)
+        .map_err(DuckDBPolarsError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_df_json_lines() {
+        let mut df = df!["a" => [1, 2], "b" => ["x", "y"]].expect("Failed to build DataFrame");
+
+        let mut buf = Vec::new();
+        write_df(&mut df, ResultFormat::JsonLines, &mut buf).expect("Failed to write df");
+
+        let actual = String::from_utf8(buf).expect("Failed to parse utf8");
+        assert_eq!(actual, "{\"a\":1,\"b\":\"x\"}\n{\"a\":2,\"b\":\"y\"}\n");
+    }
+}
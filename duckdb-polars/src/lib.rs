@@ -1,7 +1,11 @@
 pub mod export;
+pub mod sql;
 
 use std::fmt::Display;
+use std::sync::Arc;
 
+use duckdb::arrow::datatypes::{DataType as ArrowDataType, Field as ArrowField, Schema as ArrowSchema};
+use duckdb::vtab::arrow::{arrow_recordbatch_to_query_params, ArrowVTab};
 use duckdb::{arrow::record_batch::RecordBatch, Connection, Error as DuckDBError};
 use polars::export::arrow as arrow2;
 use polars::export::rayon::prelude::{
@@ -46,6 +50,32 @@ impl From<DuckDBError> for DuckDBPolarsError {
     }
 }
 
+/// Converts a single Arrow `RecordBatch` into a Polars `DataFrame`. Factored out of
+/// [`arrowrs_record_batches_to_polars_df`] so the eager and streaming query paths share the
+/// same per-batch column conversion logic.
+fn record_batch_to_df(rb: &RecordBatch, column_names: &[String]) -> Result<DataFrame, DuckDBPolarsError> {
+    let s_vec = rb
+        .columns()
+        .into_par_iter()
+        .enumerate()
+        .map(|(i, array)| {
+            let arrowrs_array_data = array.as_ref().to_data();
+            let arrow2_array = arrow2::array::from_data(&arrowrs_array_data);
+
+            let name = column_names
+                .get(i)
+                .ok_or_else(|| DuckDBPolarsError::Internal {
+                    msg: format!("Column name not found for index {}", i),
+                })?
+                .as_ref();
+
+            Series::try_from((name, arrow2_array)).map_err(DuckDBPolarsError::from)
+        })
+        .collect::<Result<Vec<_>, DuckDBPolarsError>>()?;
+
+    Ok(DataFrame::new_no_checks(s_vec))
+}
+
 pub fn arrowrs_record_batches_to_polars_df(
     rbs: Vec<RecordBatch>,
 ) -> Result<DataFrame, DuckDBPolarsError> {
@@ -58,39 +88,138 @@ pub fn arrowrs_record_batches_to_polars_df(
         .collect::<Vec<_>>();
     let dfs = rbs
         .into_par_iter()
-        .map(|rb| {
-            let s_vec = rb
-                .columns()
-                .into_par_iter()
-                .enumerate()
-                .map(|(i, array)| {
-                    let arrowrs_array_data = array.as_ref().to_data();
-                    let arrow2_array = arrow2::array::from_data(&arrowrs_array_data);
-
-                    let name = column_names
-                        .get(i)
-                        .ok_or_else(|| DuckDBPolarsError::Internal {
-                            msg: format!("Column name not found for index {}", i),
-                        })?
-                        .as_ref();
-
-                    Series::try_from((name, arrow2_array)).map_err(DuckDBPolarsError::from)
-                })
-                .collect::<Result<Vec<_>, DuckDBPolarsError>>()?;
-
-            Ok(DataFrame::new_no_checks(s_vec))
-        })
+        .map(|rb| record_batch_to_df(&rb, &column_names))
         .collect::<Result<Vec<_>, DuckDBPolarsError>>()?;
 
     Ok(accumulate_dataframes_vertical_unchecked(dfs))
 }
 
 pub fn query_to_df_polars(conn: &Connection, query: &str) -> Result<DataFrame, DuckDBPolarsError> {
+    query_to_df_polars_params(conn, query, [])
+}
+
+/// Like [`query_to_df_polars`] but lets callers bind parameters instead of string-interpolating
+/// values into `query`, e.g. `query_to_df_polars_params(conn, "SELECT * FROM t WHERE id = ?",
+/// params![id])`. Accepts anything implementing `duckdb::Params` (positional `[]`/`params![]`
+/// as well as named `named_params![...]`), mirroring the parameter-binding ergonomics
+/// rusqlite/duckdb already expose on `Statement`.
+pub fn query_to_df_polars_params(
+    conn: &Connection,
+    query: &str,
+    params: impl duckdb::Params,
+) -> Result<DataFrame, DuckDBPolarsError> {
     let mut statement = conn.prepare(query)?;
-    let rbs = statement.query_arrow([])?.collect::<Vec<_>>();
+    let rbs = statement.query_arrow(params)?.collect::<Vec<_>>();
     arrowrs_record_batches_to_polars_df(rbs)
 }
 
+/// Converts the consecutive Arrow `RecordBatch`es in `rbs` into a single `DataFrame`.
+fn record_batches_to_df(rbs: &[RecordBatch], column_names: &[String]) -> Result<DataFrame, DuckDBPolarsError> {
+    let dfs = rbs
+        .iter()
+        .map(|rb| record_batch_to_df(rb, column_names))
+        .collect::<Result<Vec<_>, DuckDBPolarsError>>()?;
+
+    Ok(accumulate_dataframes_vertical_unchecked(dfs))
+}
+
+/// Runs the prepared `statement` with `params` bound, lazily converting groups of up to
+/// `batch_window` Arrow `RecordBatch`es into one `DataFrame` at a time, keeping peak memory
+/// bounded by that window instead of collecting the whole result set up front like
+/// [`query_to_df_polars`] does.
+///
+/// Takes a prepared `statement` rather than `(conn, query)` directly so the returned iterator
+/// can borrow from it for its whole lifetime; callers keep the statement alive for as long as
+/// they drive the stream.
+pub fn query_to_df_stream<'stmt>(
+    statement: &'stmt mut duckdb::Statement<'_>,
+    params: impl duckdb::Params,
+    batch_window: usize,
+) -> Result<impl Iterator<Item = Result<DataFrame, DuckDBPolarsError>> + 'stmt, DuckDBPolarsError> {
+    assert!(batch_window > 0, "batch_window must be at least 1");
+
+    let column_names = statement
+        .column_names()
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    let mut arrow_iter = statement.query_arrow(params)?;
+
+    Ok(std::iter::from_fn(move || {
+        let batch = arrow_iter.by_ref().take(batch_window).collect::<Vec<_>>();
+        if batch.is_empty() {
+            None
+        } else {
+            Some(record_batches_to_df(&batch, &column_names))
+        }
+    }))
+}
+
+/// Builds an arrow-rs [`ArrowSchema`] from a Polars [`Schema`], the data-type counterpart to
+/// the array conversion in [`polars_df_to_arrowrs_record_batches`].
+fn polars_schema_to_arrowrs_schema(schema: &Schema) -> ArrowSchema {
+    let fields = schema
+        .iter_fields()
+        .map(|f| {
+            let arrow2_field = f.to_arrow(CompatLevel::newest());
+            let data_type: ArrowDataType = (&arrow2_field.data_type).into();
+            ArrowField::new(f.name().as_str(), data_type, arrow2_field.is_nullable)
+        })
+        .collect::<Vec<_>>();
+
+    ArrowSchema::new(fields)
+}
+
+/// Converts a Polars `DataFrame` into arrow-rs `RecordBatch`es, one per Polars chunk, by
+/// converting each chunk's `arrow2` arrays back to arrow-rs `ArrayData`. This is the inverse
+/// of the `arrow2::array::from_data` conversion used in [`arrowrs_record_batches_to_polars_df`].
+fn polars_df_to_arrowrs_record_batches(df: &DataFrame) -> Result<Vec<RecordBatch>, DuckDBPolarsError> {
+    let arrow_schema = Arc::new(polars_schema_to_arrowrs_schema(&df.schema()));
+
+    df.iter_chunks(CompatLevel::newest(), true)
+        .map(|chunk| {
+            let columns = chunk
+                .into_arrays()
+                .into_iter()
+                .map(|arrow2_array| {
+                    let array_data = arrow2::array::to_data(arrow2_array.as_ref());
+                    duckdb::arrow::array::make_array(array_data)
+                })
+                .collect::<Vec<_>>();
+
+            RecordBatch::try_new(arrow_schema.clone(), columns).map_err(|e| DuckDBPolarsError::Internal {
+                msg: format!("Failed to build RecordBatch from Polars chunk: {}", e),
+            })
+        })
+        .collect()
+}
+
+/// Registers a Polars `DataFrame` as a named table, so it can be queried with arbitrary
+/// DuckDB SQL (joins across several registered frames, window functions, etc.). This is the
+/// inverse of [`query_to_df_polars`]: instead of pulling DuckDB results into Polars, it hands
+/// an already-materialized Polars frame to DuckDB.
+pub fn register_df(conn: &Connection, name: &str, df: &DataFrame) -> Result<(), DuckDBPolarsError> {
+    let rbs = polars_df_to_arrowrs_record_batches(df)?;
+
+    // `arrow` is a fixed table function name shared by every registered frame; registering it
+    // twice on the same connection errors, so ignore failure here and only surface errors from
+    // the table creation below.
+    let _ = conn.register_table_function::<ArrowVTab>("arrow");
+
+    // `arrow_recordbatch_to_query_params` binds two values (the array-stream pointer and the
+    // schema pointer), so the table function call needs two placeholders. The stream is
+    // single-shot, so materialize it into a table immediately rather than backing a view with
+    // it: a view would only be scannable once, and every later read (joins, repeated queries)
+    // would see an exhausted stream.
+    let params = arrow_recordbatch_to_query_params(rbs);
+    conn.execute(
+        &format!(r#"CREATE OR REPLACE TABLE "{name}" AS SELECT * FROM arrow(?, ?)"#),
+        params,
+    )?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
@@ -165,4 +294,69 @@ mod tests {
     fn test_query_to_df_polars(connection: &Connection, #[case] test_spec: TestSpec) {
         test_spec.run(connection);
     }
+
+    #[rstest]
+    fn test_register_df(connection: &Connection) {
+        let df = df![
+            "a" => [1, 2, 3],
+            "b" => ["x", "y", "z"],
+        ]
+        .expect("Failed to build DataFrame");
+
+        register_df(connection, "people", &df).expect("Failed to register df");
+
+        let mut result = query_to_df_polars(connection, "SELECT * FROM people ORDER BY a")
+            .expect("Failed to query registered df");
+
+        let mut buf = Vec::new();
+        JsonWriter::new(&mut buf)
+            .with_json_format(JsonFormat::JsonLines)
+            .finish(&mut result)
+            .expect("Failed to serialize df");
+
+        let actual = String::from_utf8(buf).expect("Failed to parse utf8");
+        assert_eq!(
+            actual,
+            "{\"a\":1,\"b\":\"x\"}\n{\"a\":2,\"b\":\"y\"}\n{\"a\":3,\"b\":\"z\"}\n"
+        );
+
+        // The table must be re-scannable, not a view backed by a single-shot Arrow stream.
+        let count = query_to_df_polars(connection, "SELECT COUNT(*) AS c FROM people")
+            .expect("Failed to re-query registered df");
+        assert_eq!(count.column("c").unwrap().get(0).unwrap(), AnyValue::Int64(3));
+    }
+
+    #[rstest]
+    fn test_query_to_df_stream(connection: &Connection) {
+        let mut statement = connection
+            .prepare("SELECT * FROM range(3) AS t(a) WHERE a >= ?")
+            .expect("Failed to prepare statement");
+
+        let dfs = query_to_df_stream(&mut statement, duckdb::params![0], 1)
+            .expect("Failed to start stream")
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Failed to collect stream");
+
+        let df = accumulate_dataframes_vertical_unchecked(dfs);
+        assert_eq!(df.column("a").unwrap().i64().unwrap().get(1), Some(1));
+    }
+
+    #[rstest]
+    fn test_query_to_df_polars_params(connection: &Connection) {
+        let mut df = query_to_df_polars_params(
+            connection,
+            "SELECT * FROM (VALUES (1, 'x'), (2, 'y')) AS t(a, b) WHERE a = ?",
+            duckdb::params![2],
+        )
+        .expect("Failed to run parameterized query");
+
+        let mut buf = Vec::new();
+        JsonWriter::new(&mut buf)
+            .with_json_format(JsonFormat::JsonLines)
+            .finish(&mut df)
+            .expect("Failed to serialize df");
+
+        let actual = String::from_utf8(buf).expect("Failed to parse utf8");
+        assert_eq!(actual, "{\"a\":2,\"b\":\"y\"}\n");
+    }
 }
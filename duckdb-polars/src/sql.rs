@@ -0,0 +1,391 @@
+//! A secondary, in-process SQL layer over already-materialized Polars frames. Lets callers
+//! reshape a `DataFrame` (e.g. one returned by [`crate::query_to_df_polars`]) with further SQL
+//! without round-tripping through DuckDB again. `SELECT`/`WHERE`/`GROUP BY`/`ORDER BY`/`LIMIT`
+//! are parsed with `sqlparser` and translated into `polars` lazy expressions on a `LazyFrame`,
+//! which is only collected at the end.
+
+use std::collections::HashMap;
+
+use polars::prelude::*;
+use sqlparser::ast::{
+    BinaryOperator, Expr as SqlExpr, Function, FunctionArg, FunctionArgExpr, Select, SelectItem,
+    SetExpr, Statement as SqlStatement, TableFactor, Value,
+};
+use sqlparser::dialect::GenericDialect;
+use sqlparser::parser::Parser as SqlParser;
+
+use crate::DuckDBPolarsError;
+
+fn sql_err(msg: impl Into<String>) -> DuckDBPolarsError {
+    DuckDBPolarsError::Internal { msg: msg.into() }
+}
+
+/// An in-process SQL layer over named, already-materialized Polars frames.
+#[derive(Debug, Default)]
+pub struct DuckDBPolars {
+    frames: HashMap<String, DataFrame>,
+}
+
+impl DuckDBPolars {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `df` under `name` so it can be referenced as a table in [`Self::sql`].
+    pub fn register(&mut self, name: &str, df: DataFrame) {
+        self.frames.insert(name.to_string(), df);
+    }
+
+    /// Runs a `SELECT` query against the registered frames, translating it into a `polars`
+    /// `LazyFrame` pipeline rather than executing real SQL.
+    pub fn sql(&self, query: &str) -> Result<DataFrame, DuckDBPolarsError> {
+        let mut statements = SqlParser::parse_sql(&GenericDialect {}, query)
+            .map_err(|e| sql_err(format!("Failed to parse SQL: {}", e)))?;
+
+        if statements.len() != 1 {
+            return Err(sql_err("Expected exactly one SQL statement"));
+        }
+
+        let query = match statements.remove(0) {
+            SqlStatement::Query(query) => query,
+            other => return Err(sql_err(format!("Unsupported statement: {other}"))),
+        };
+
+        let select = match *query.body {
+            SetExpr::Select(select) => select,
+            other => return Err(sql_err(format!("Unsupported query body: {other}"))),
+        };
+
+        let mut lf = self.table(&select)?;
+
+        if let Some(selection) = &select.selection {
+            lf = lf.filter(sql_expr_to_polars(selection)?);
+        }
+
+        lf = if select.group_by.is_empty() {
+            self.project(lf, &select.projection)?
+        } else {
+            self.group_by(lf, &select.group_by, &select.projection)?
+        };
+
+        if !query.order_by.is_empty() {
+            let (exprs, descending): (Vec<_>, Vec<_>) = query
+                .order_by
+                .iter()
+                .map(|order_by| {
+                    sql_expr_to_polars(&order_by.expr).map(|e| (e, !order_by.asc.unwrap_or(true)))
+                })
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .unzip();
+            lf = lf.sort_by_exprs(exprs, descending, false, false);
+        }
+
+        if let Some(limit) = &query.limit {
+            lf = lf.limit(sql_expr_to_u32(limit)?);
+        }
+
+        lf.collect().map_err(DuckDBPolarsError::from)
+    }
+
+    fn table(&self, select: &Select) -> Result<LazyFrame, DuckDBPolarsError> {
+        let table = select
+            .from
+            .first()
+            .ok_or_else(|| sql_err("Query has no FROM clause"))?;
+
+        let name = match &table.relation {
+            TableFactor::Table { name, .. } => name.to_string(),
+            other => return Err(sql_err(format!("Unsupported FROM clause: {other}"))),
+        };
+
+        self.frames
+            .get(&name)
+            .cloned()
+            .map(IntoLazy::lazy)
+            .ok_or_else(|| sql_err(format!("No frame registered under name {name:?}")))
+    }
+
+    fn project(&self, lf: LazyFrame, projection: &[SelectItem]) -> Result<LazyFrame, DuckDBPolarsError> {
+        let exprs = select_items_to_polars(projection)?;
+        Ok(match exprs {
+            Some(exprs) => lf.select(exprs),
+            None => lf,
+        })
+    }
+
+    fn group_by(
+        &self,
+        lf: LazyFrame,
+        group_by: &[SqlExpr],
+        projection: &[SelectItem],
+    ) -> Result<LazyFrame, DuckDBPolarsError> {
+        let keys = group_by
+            .iter()
+            .map(sql_expr_to_polars)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let key_names = group_by
+            .iter()
+            .map(sql_expr_identifier_name)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Every projected item must either be one of the GROUP BY keys (already covered by
+        // `keys` above) or an aggregate function; anything else can't be translated and must
+        // be rejected rather than silently dropped from the output.
+        let mut aggs = Vec::new();
+        for item in projection {
+            let (expr, alias) = match item {
+                SelectItem::UnnamedExpr(expr) => (expr, None),
+                SelectItem::ExprWithAlias { expr, alias } => (expr, Some(alias.value.as_str())),
+                other => {
+                    return Err(sql_err(format!(
+                        "Unsupported GROUP BY projection item: {other}"
+                    )))
+                }
+            };
+
+            match expr {
+                SqlExpr::Function(f) => {
+                    let agg = function_to_polars(f)?;
+                    aggs.push(match alias {
+                        Some(alias) => agg.alias(alias),
+                        None => agg,
+                    });
+                }
+                SqlExpr::Identifier(_) | SqlExpr::CompoundIdentifier(_) => {
+                    let name = sql_expr_identifier_name(expr)?;
+                    if !key_names.contains(&name) {
+                        return Err(sql_err(format!(
+                            "Column {name:?} must appear in GROUP BY or be an aggregate"
+                        )));
+                    }
+                }
+                other => {
+                    return Err(sql_err(format!(
+                        "Unsupported GROUP BY projection expression: {other}"
+                    )))
+                }
+            }
+        }
+
+        Ok(lf.group_by(keys).agg(aggs))
+    }
+}
+
+/// Extracts the plain column name from an identifier expression, erroring on anything else.
+fn sql_expr_identifier_name(expr: &SqlExpr) -> Result<String, DuckDBPolarsError> {
+    match expr {
+        SqlExpr::Identifier(ident) => Ok(ident.value.clone()),
+        SqlExpr::CompoundIdentifier(parts) => parts
+            .last()
+            .map(|part| part.value.clone())
+            .ok_or_else(|| sql_err("Empty compound identifier")),
+        other => Err(sql_err(format!("Expected a column reference, got: {other}"))),
+    }
+}
+
+/// Translates a `SELECT` item list into `polars` projection expressions. A bare `*` wildcard
+/// translates to "keep the LazyFrame as-is" (`None`).
+fn select_items_to_polars(items: &[SelectItem]) -> Result<Option<Vec<Expr>>, DuckDBPolarsError> {
+    let mut exprs = Vec::with_capacity(items.len());
+    for item in items {
+        match item {
+            SelectItem::Wildcard(_) => return Ok(None),
+            SelectItem::UnnamedExpr(expr) => exprs.push(sql_expr_to_polars(expr)?),
+            SelectItem::ExprWithAlias { expr, alias } => {
+                exprs.push(sql_expr_to_polars(expr)?.alias(&alias.value))
+            }
+            other => return Err(sql_err(format!("Unsupported select item: {other}"))),
+        }
+    }
+    Ok(Some(exprs))
+}
+
+/// Translates a `WHERE`/projection/`GROUP BY` scalar `Expr` into a `polars` lazy `Expr`.
+fn sql_expr_to_polars(expr: &SqlExpr) -> Result<Expr, DuckDBPolarsError> {
+    match expr {
+        SqlExpr::Identifier(ident) => Ok(col(&ident.value)),
+        SqlExpr::CompoundIdentifier(parts) => {
+            let name = parts
+                .last()
+                .ok_or_else(|| sql_err("Empty compound identifier"))?;
+            Ok(col(&name.value))
+        }
+        SqlExpr::Value(value) => sql_value_to_polars(value),
+        SqlExpr::Function(f) => function_to_polars(f),
+        SqlExpr::BinaryOp { left, op, right } => {
+            let left = sql_expr_to_polars(left)?;
+            let right = sql_expr_to_polars(right)?;
+            Ok(match op {
+                BinaryOperator::Eq => left.eq(right),
+                BinaryOperator::NotEq => left.neq(right),
+                BinaryOperator::Lt => left.lt(right),
+                BinaryOperator::LtEq => left.lt_eq(right),
+                BinaryOperator::Gt => left.gt(right),
+                BinaryOperator::GtEq => left.gt_eq(right),
+                BinaryOperator::And => left.and(right),
+                BinaryOperator::Or => left.or(right),
+                BinaryOperator::Plus => left + right,
+                BinaryOperator::Minus => left - right,
+                BinaryOperator::Multiply => left * right,
+                BinaryOperator::Divide => left / right,
+                other => return Err(sql_err(format!("Unsupported binary operator: {other}"))),
+            })
+        }
+        other => Err(sql_err(format!("Unsupported expression: {other}"))),
+    }
+}
+
+fn sql_value_to_polars(value: &Value) -> Result<Expr, DuckDBPolarsError> {
+    match value {
+        // Integer literals stay integers so e.g. `id = 2` compares against `col("id")` as an
+        // i64 rather than forcing an implicit float cast; only fall back to f64 when the
+        // literal actually has a fractional part.
+        Value::Number(n, _) if !n.contains('.') => n
+            .parse::<i64>()
+            .map(lit)
+            .map_err(|e| sql_err(format!("Invalid numeric literal {n:?}: {e}"))),
+        Value::Number(n, _) => n
+            .parse::<f64>()
+            .map(lit)
+            .map_err(|e| sql_err(format!("Invalid numeric literal {n:?}: {e}"))),
+        Value::SingleQuotedString(s) | Value::DoubleQuotedString(s) => Ok(lit(s.clone())),
+        Value::Boolean(b) => Ok(lit(*b)),
+        Value::Null => Ok(lit(NULL)),
+        other => Err(sql_err(format!("Unsupported literal: {other}"))),
+    }
+}
+
+fn function_to_polars(func: &Function) -> Result<Expr, DuckDBPolarsError> {
+    let name = func.name.to_string().to_uppercase();
+
+    if name == "COUNT" {
+        // `COUNT(*)` has no column argument to translate.
+        return Ok(count());
+    }
+
+    let arg = func
+        .args
+        .first()
+        .ok_or_else(|| sql_err(format!("{name} requires exactly one argument")))?;
+
+    let expr = match arg {
+        FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) => sql_expr_to_polars(expr)?,
+        other => return Err(sql_err(format!("Unsupported function argument: {other}"))),
+    };
+
+    match name.as_str() {
+        "SUM" => Ok(expr.sum()),
+        "AVG" => Ok(expr.mean()),
+        "MIN" => Ok(expr.min()),
+        "MAX" => Ok(expr.max()),
+        other => Err(sql_err(format!("Unsupported aggregate function: {other}"))),
+    }
+}
+
+fn sql_expr_to_u32(expr: &SqlExpr) -> Result<u32, DuckDBPolarsError> {
+    match expr {
+        SqlExpr::Value(Value::Number(n, _)) => n
+            .parse::<u32>()
+            .map_err(|e| sql_err(format!("Invalid LIMIT {n:?}: {e}"))),
+        other => Err(sql_err(format!("Unsupported LIMIT expression: {other}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sql_select_where_order_limit() {
+        let mut ctx = DuckDBPolars::new();
+        ctx.register(
+            "people",
+            df!["name" => ["a", "b", "c"], "age" => [30, 20, 40]].expect("Failed to build DataFrame"),
+        );
+
+        let df = ctx
+            .sql("SELECT name, age FROM people WHERE age > 20 ORDER BY age LIMIT 1")
+            .expect("Failed to run post-SQL");
+
+        assert_eq!(df.column("name").unwrap().get(0).unwrap(), AnyValue::String("a"));
+    }
+
+    #[test]
+    fn test_sql_multi_column_order_by() {
+        let mut ctx = DuckDBPolars::new();
+        ctx.register(
+            "people",
+            df!["a" => [1, 1, 2], "b" => [2, 1, 0]].expect("Failed to build DataFrame"),
+        );
+
+        // `a` is the primary sort key; `b` only breaks ties within equal `a`.
+        let df = ctx
+            .sql("SELECT a, b FROM people ORDER BY a, b")
+            .expect("Failed to run post-SQL");
+
+        assert_eq!(
+            df.column("a").unwrap().i32().unwrap().into_no_null_iter().collect::<Vec<_>>(),
+            vec![1, 1, 2]
+        );
+        assert_eq!(
+            df.column("b").unwrap().i32().unwrap().into_no_null_iter().collect::<Vec<_>>(),
+            vec![1, 2, 0]
+        );
+    }
+
+    #[test]
+    fn test_sql_group_by_agg() {
+        let mut ctx = DuckDBPolars::new();
+        ctx.register(
+            "orders",
+            df!["region" => ["east", "east", "west"], "amount" => [10, 20, 5]]
+                .expect("Failed to build DataFrame"),
+        );
+
+        let df = ctx
+            .sql("SELECT region, SUM(amount) AS total FROM orders GROUP BY region")
+            .expect("Failed to run post-SQL");
+
+        let total = df
+            .clone()
+            .lazy()
+            .filter(col("region").eq(lit("east")))
+            .select([col("total")])
+            .collect()
+            .unwrap();
+        assert_eq!(total.column("total").unwrap().get(0).unwrap(), AnyValue::Int32(30));
+    }
+
+    #[test]
+    fn test_sql_group_by_rejects_non_key_non_aggregate_column() {
+        let mut ctx = DuckDBPolars::new();
+        ctx.register(
+            "orders",
+            df!["region" => ["east", "west"], "amount" => [10, 5]].expect("Failed to build DataFrame"),
+        );
+
+        let err = ctx
+            .sql("SELECT region, amount, SUM(amount) AS total FROM orders GROUP BY region")
+            .expect_err("Should reject a non-key, non-aggregate column instead of dropping it");
+        assert!(err.to_string().contains("amount"));
+    }
+
+    #[test]
+    fn test_sql_integer_literal_equality() {
+        let mut ctx = DuckDBPolars::new();
+        ctx.register(
+            "people",
+            df!["id" => [9_007_199_254_740_993_i64, 2]].expect("Failed to build DataFrame"),
+        );
+
+        // A large i64 loses precision once round-tripped through f64, so this only passes if
+        // the literal stays an integer instead of being parsed as f64.
+        let df = ctx
+            .sql("SELECT id FROM people WHERE id = 9007199254740993")
+            .expect("Failed to run post-SQL");
+
+        assert_eq!(df.height(), 1);
+    }
+}